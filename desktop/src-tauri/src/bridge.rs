@@ -0,0 +1,102 @@
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::routing::get;
+use axum::Router;
+use tower::{Service, ServiceExt};
+
+use crate::settings::Endpoint;
+
+// Build the in-process API router for the given endpoint. The router serves
+// what it can in-process and forwards the rest to the configured daemon, so the
+// `ugudu://` path keeps working after the endpoint is reconfigured at runtime.
+pub fn build_router(endpoint: Endpoint) -> Router {
+    Router::new()
+        .route("/api/health", get(|| async { "ok" }))
+        .fallback(proxy_to_daemon)
+        .with_state(endpoint)
+}
+
+// Forward any request the in-process router does not handle to the configured
+// ugudu daemon, preserving the method, headers, and body so real API calls
+// (including POSTs) reach an already-running daemon unchanged.
+async fn proxy_to_daemon(State(endpoint): State<Endpoint>, req: Request) -> axum::response::Response {
+    let (parts, body) = req.into_parts();
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or("/");
+    let url = format!(
+        "{}://{}:{}{}",
+        endpoint.scheme, endpoint.host, endpoint.port, path
+    );
+
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return axum::response::Response::builder()
+                .status(400)
+                .body(Body::from(format!("invalid request body: {}", e)))
+                .unwrap()
+        }
+    };
+
+    let outgoing = reqwest::Client::new()
+        .request(parts.method, url)
+        .headers(parts.headers)
+        .body(bytes)
+        .send()
+        .await;
+
+    match outgoing {
+        Ok(resp) => {
+            let status = resp.status();
+            let headers = resp.headers().clone();
+            let body = resp.bytes().await.unwrap_or_default();
+            let mut builder = axum::response::Response::builder().status(status);
+            for (name, value) in headers.iter() {
+                builder = builder.header(name, value);
+            }
+            builder.body(Body::from(body)).unwrap()
+        }
+        Err(e) => axum::response::Response::builder()
+            .status(502)
+            .body(Body::from(format!("daemon unreachable: {}", e)))
+            .unwrap(),
+    }
+}
+
+// Drive one request from the Tauri custom-scheme handler through the axum
+// router and convert the result back into a Tauri HTTP response.
+//
+// The tower `Service` needs `&mut self`, so the caller guards the router with a
+// mutex and hands us a mutable borrow.
+pub async fn dispatch(
+    router: &mut Router,
+    request: tauri::http::Request<Vec<u8>>,
+) -> tauri::http::Response<Vec<u8>> {
+    let (parts, body) = request.into_parts();
+    let req = Request::from_parts(parts, Body::from(body));
+
+    let response = match router.as_service().ready().await {
+        Ok(service) => match service.call(req).await {
+            Ok(response) => response,
+            Err(_) => error_response(),
+        },
+        Err(_) => error_response(),
+    };
+
+    let (parts, body) = response.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map(|b| b.to_vec())
+        .unwrap_or_default();
+    tauri::http::Response::from_parts(parts, bytes)
+}
+
+fn error_response() -> axum::response::Response {
+    axum::response::Response::builder()
+        .status(500)
+        .body(Body::from("internal bridge error"))
+        .unwrap()
+}
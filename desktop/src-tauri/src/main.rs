@@ -1,92 +1,321 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::process::Command;
+mod bridge;
+mod settings;
+
+use std::path::PathBuf;
 use std::time::Duration;
-use tauri::Manager;
 
-// Check if the daemon is running by attempting to connect
-async fn check_daemon() -> bool {
-    match reqwest::get("http://localhost:8080/api/health").await {
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+
+use settings::Settings;
+
+// Event channel the frontend subscribes to for live bootstrap progress.
+const DAEMON_STATUS_EVENT: &str = "daemon://status";
+
+// Structured daemon lifecycle status, emitted during bootstrap and returned by
+// `get_daemon_status`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+enum DaemonStatus {
+    // The daemon process is being spawned.
+    Spawning,
+    // The process is up; we are polling its health endpoint.
+    WaitingForHealth,
+    // The daemon responded healthy.
+    Ready,
+    // Bootstrap gave up; `reason` explains why.
+    Failed { reason: String },
+}
+
+// Application state managed by Tauri. Holds the loaded settings and the handle
+// to the daemon process we spawned, so we can stop/restart it and distinguish
+// our own daemon from an unrelated one listening on the same port.
+struct AppState {
+    // Settings are behind a mutex so the endpoint can be changed and persisted
+    // at runtime.
+    settings: Mutex<Settings>,
+    config_dir: PathBuf,
+    daemon: Mutex<Option<Child>>,
+    // In-process API router, guarded by a mutex because the tower `Service`
+    // driven by the `ugudu://` scheme handler needs `&mut self`.
+    bridge: Mutex<axum::Router>,
+    // Held for the duration of a bootstrap so concurrent `ensure_daemon` /
+    // setup calls don't race two spawns against the same port.
+    bootstrap_lock: Mutex<()>,
+}
+
+// Check if the daemon is running by attempting to connect to its health URL.
+async fn check_daemon(endpoint: &settings::Endpoint) -> bool {
+    match reqwest::get(endpoint.health_url()).await {
         Ok(resp) => resp.status().is_success(),
         Err(_) => false,
     }
 }
 
-// Start the daemon process
-fn start_daemon() -> Result<(), String> {
-    // Try to find ugudu binary
+// Resolve the daemon binary, honouring (in order) an explicit settings
+// override, the system PATH, then the common install locations.
+fn resolve_daemon_binary(settings: &Settings) -> Option<PathBuf> {
     let binary = if cfg!(target_os = "windows") {
         "ugudu.exe"
     } else {
         "ugudu"
     };
 
-    // Try common locations
-    let paths = vec![
-        format!("/usr/local/bin/{}", binary),
-        format!("{}/.local/bin/{}", std::env::var("HOME").unwrap_or_default(), binary),
-        format!("{}/go/bin/{}", std::env::var("HOME").unwrap_or_default(), binary),
-        binary.to_string(),
+    // Explicit override wins, provided it actually exists.
+    if let Some(path) = &settings.daemon_path {
+        if path.exists() {
+            return Some(path.clone());
+        }
+    }
+
+    // Primary strategy: look the binary up on PATH.
+    if let Some(path) = pathsearch::find_executable_in_path(binary) {
+        return Some(path);
+    }
+
+    // Fall back to the common install locations.
+    let home = std::env::var("HOME").unwrap_or_default();
+    let candidates = [
+        PathBuf::from(format!("/usr/local/bin/{}", binary)),
+        PathBuf::from(format!("{}/.local/bin/{}", home, binary)),
+        PathBuf::from(format!("{}/go/bin/{}", home, binary)),
     ];
+    candidates.into_iter().find(|p| p.exists())
+}
+
+// Start the daemon process and track the resulting child so it is killed when
+// the app exits (`kill_on_drop`) and can be stopped/restarted on demand.
+//
+// Before spawning, the configured port is probed: if something is already
+// listening there and it is not a healthy ugudu daemon, a distinct error is
+// surfaced rather than spawning and polling fruitlessly.
+async fn start_daemon(state: &AppState) -> Result<(), String> {
+    let endpoint = state.settings.lock().await.endpoint.clone();
 
-    for path in paths {
-        if let Ok(_) = Command::new(&path)
-            .arg("daemon")
-            .spawn()
-        {
-            return Ok(());
+    match tokio::net::TcpListener::bind(endpoint.bind_addr()).await {
+        // Port is free: drop the probe listener and start the daemon.
+        Ok(listener) => drop(listener),
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+            if check_daemon(&endpoint).await {
+                // A healthy daemon already owns the port — nothing to do.
+                return Ok(());
+            }
+            return Err(format!(
+                "Port {} is already in use by another process",
+                endpoint.port
+            ));
         }
+        Err(e) => return Err(format!("Could not probe daemon port: {}", e)),
     }
 
-    Err("Could not find or start ugudu daemon".to_string())
+    let binary = resolve_daemon_binary(&state.settings.lock().await)
+        .ok_or_else(|| "Could not find ugudu daemon".to_string())?;
+
+    // Tell the daemon which port to bind so it matches the configured endpoint
+    // (and the port we just probed), rather than its built-in default.
+    let child = Command::new(&binary)
+        .arg("daemon")
+        .arg("--port")
+        .arg(endpoint.port.to_string())
+        .env("UGUDU_PORT", endpoint.port.to_string())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Could not start ugudu daemon: {}", e))?;
+
+    *state.daemon.lock().await = Some(child);
+    Ok(())
+}
+
+// Report whether the daemon we spawned is still alive. Unlike `check_daemon`,
+// this inspects the tracked child's exit status, so we can tell "we started it
+// and it crashed" apart from "someone else's daemon is listening on the
+// configured port".
+async fn daemon_running(state: &AppState) -> bool {
+    let mut guard = state.daemon.lock().await;
+    match guard.as_mut() {
+        Some(child) => matches!(child.try_wait(), Ok(None)),
+        None => false,
+    }
 }
 
 #[tauri::command]
-async fn get_daemon_status() -> Result<String, String> {
-    if check_daemon().await {
-        Ok("running".to_string())
+async fn get_daemon_status(state: tauri::State<'_, AppState>) -> Result<DaemonStatus, String> {
+    let endpoint = state.settings.lock().await.endpoint.clone();
+    if check_daemon(&endpoint).await {
+        Ok(DaemonStatus::Ready)
+    } else if daemon_running(&state).await {
+        Ok(DaemonStatus::WaitingForHealth)
     } else {
-        Err("Daemon is not running".to_string())
+        Ok(DaemonStatus::Failed {
+            reason: "daemon is not running".to_string(),
+        })
     }
 }
 
-#[tauri::command]
-async fn ensure_daemon() -> Result<String, String> {
-    if check_daemon().await {
-        return Ok("already_running".to_string());
+// Drive the daemon to readiness in the background, emitting `daemon://status`
+// events so the UI can render a live splash/status indicator. Health probes
+// use exponential backoff up to the configured overall timeout.
+async fn bootstrap_daemon(app: AppHandle) {
+    let emit = |status: DaemonStatus| {
+        let _ = app.emit(DAEMON_STATUS_EVENT, status);
+    };
+
+    let state = app.state::<AppState>();
+
+    // Only one bootstrap at a time; a concurrent caller just bows out.
+    let _guard = match state.bootstrap_lock.try_lock() {
+        Ok(guard) => guard,
+        Err(_) => return,
+    };
+
+    let (endpoint, timeout) = {
+        let settings = state.settings.lock().await;
+        (
+            settings.endpoint.clone(),
+            Duration::from_secs(settings.readiness_timeout_secs),
+        )
+    };
+
+    if check_daemon(&endpoint).await {
+        emit(DaemonStatus::Ready);
+        return;
+    }
+
+    emit(DaemonStatus::Spawning);
+    if let Err(reason) = start_daemon(&state).await {
+        emit(DaemonStatus::Failed { reason });
+        return;
     }
 
-    // Try to start the daemon
-    start_daemon()?;
+    let mut delay = Duration::from_millis(250);
+    let mut elapsed = Duration::ZERO;
+    loop {
+        emit(DaemonStatus::WaitingForHealth);
+        tokio::time::sleep(delay).await;
+        elapsed += delay;
 
-    // Wait for daemon to be ready
-    for _ in 0..30 {
-        tokio::time::sleep(Duration::from_millis(500)).await;
-        if check_daemon().await {
-            return Ok("started".to_string());
+        if check_daemon(&endpoint).await {
+            emit(DaemonStatus::Ready);
+            return;
+        }
+        if elapsed >= timeout {
+            emit(DaemonStatus::Failed {
+                reason: format!("daemon not healthy after {:?}", timeout),
+            });
+            return;
         }
+        // Exponential backoff, capped so we keep probing reasonably often.
+        delay = (delay * 2).min(Duration::from_secs(4));
+    }
+}
+
+// Report whether a daemon binary could be resolved, so the frontend can show
+// an install-needed prompt before calling `ensure_daemon`.
+#[tauri::command]
+async fn has_executable(state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    Ok(resolve_daemon_binary(&state.settings.lock().await).is_some())
+}
+
+// Change the daemon port at runtime, persist it, and apply it to the live API
+// path: the bridge router is rebuilt so `ugudu://` proxies to the new port, and
+// the daemon is restarted so it listens there.
+#[tauri::command]
+async fn set_port(port: u16, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let endpoint = {
+        let mut settings = state.settings.lock().await;
+        settings.endpoint.port = port;
+        settings.save(&state.config_dir)?;
+        settings.endpoint.clone()
+    };
+
+    // Rebuild the stateless bridge router so it targets the new endpoint.
+    *state.bridge.lock().await = bridge::build_router(endpoint);
+
+    // Restart the daemon so it binds the new port (no-op if none is running).
+    if daemon_running(&state).await {
+        stop_daemon_inner(&state).await?;
+        start_daemon(&state).await?;
+    }
+    Ok(())
+}
+
+// Stop the daemon we spawned, if any. Takes the handle out with `mem::take` so
+// a subsequent `daemon_running` check reports it as gone.
+async fn stop_daemon_inner(state: &AppState) -> Result<(), String> {
+    if let Some(mut child) = std::mem::take(&mut *state.daemon.lock().await) {
+        child.kill().await.map_err(|e| e.to_string())?;
     }
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_daemon(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    stop_daemon_inner(&state).await
+}
 
-    Err("Daemon failed to start".to_string())
+// Stop and start the daemon again.
+#[tauri::command]
+async fn restart_daemon(state: tauri::State<'_, AppState>) -> Result<String, String> {
+    stop_daemon_inner(&state).await?;
+    start_daemon(&state).await?;
+    Ok("restarted".to_string())
+}
+
+// Kick off daemon bootstrap in the background and return immediately. Progress
+// is reported through `daemon://status` events; the frontend subscribes rather
+// than blocking on this call.
+#[tauri::command]
+async fn ensure_daemon(app: AppHandle) -> Result<(), String> {
+    tauri::async_runtime::spawn(bootstrap_daemon(app));
+    Ok(())
 }
 
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .invoke_handler(tauri::generate_handler![get_daemon_status, ensure_daemon])
-        .setup(|app| {
-            // Get the main window
-            let window = app.get_webview_window("main").unwrap();
-
-            // Spawn a task to ensure daemon is running
+        // Bridge the daemon API in-process over `ugudu://` so the frontend can
+        // `fetch("ugudu://localhost/api/...")` with no TCP port and no network
+        // exposure.
+        .register_asynchronous_uri_scheme_protocol("ugudu", |ctx, request, responder| {
+            let handle = ctx.app_handle().clone();
             tauri::async_runtime::spawn(async move {
-                if !check_daemon().await {
-                    let _ = start_daemon();
-                    // Wait a bit for daemon to start
-                    tokio::time::sleep(Duration::from_secs(2)).await;
-                }
+                let state = handle.state::<AppState>();
+                let mut router = state.bridge.lock().await;
+                responder.respond(bridge::dispatch(&mut router, request).await);
             });
+        })
+        .invoke_handler(tauri::generate_handler![
+            get_daemon_status,
+            ensure_daemon,
+            has_executable,
+            stop_daemon,
+            restart_daemon,
+            set_port
+        ])
+        .setup(|app| {
+            // Load persisted settings from the app config dir.
+            let config_dir = app
+                .path()
+                .app_config_dir()
+                .expect("failed to resolve app config dir");
+            let settings = Settings::load(&config_dir);
+            let bridge = bridge::build_router(settings.endpoint.clone());
+            app.manage(AppState {
+                settings: Mutex::new(settings),
+                config_dir,
+                daemon: Mutex::new(None),
+                bridge: Mutex::new(bridge),
+                bootstrap_lock: Mutex::new(()),
+            });
+
+            // Drive the daemon to readiness in the background, emitting status
+            // events the frontend can render as a live splash.
+            tauri::async_runtime::spawn(bootstrap_daemon(app.handle().clone()));
 
             Ok(())
         })
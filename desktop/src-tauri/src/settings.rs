@@ -0,0 +1,84 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+// Persisted application settings, loaded once at startup from a TOML file in
+// the platform config dir (e.g. `~/.config/ugudu/settings.toml` on Linux).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Explicit override for the daemon binary. When set, it takes precedence
+    // over PATH discovery and the common install locations.
+    pub daemon_path: Option<PathBuf>,
+    // Where the daemon listens / is reached.
+    pub endpoint: Endpoint,
+    // Overall budget for the daemon to become healthy during bootstrap.
+    pub readiness_timeout_secs: u64,
+}
+
+// A freshly-defaulted `Settings` uses a 15 second readiness budget.
+fn default_readiness_timeout() -> u64 {
+    15
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            daemon_path: None,
+            endpoint: Endpoint::default(),
+            readiness_timeout_secs: default_readiness_timeout(),
+        }
+    }
+}
+
+// The daemon's network endpoint. Used both to build health/API URLs and to
+// probe port availability before spawning a fresh daemon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Endpoint {
+    pub scheme: String,
+    pub host: String,
+    pub port: u16,
+}
+
+impl Default for Endpoint {
+    fn default() -> Self {
+        Self {
+            scheme: "http".to_string(),
+            host: "localhost".to_string(),
+            port: 8080,
+        }
+    }
+}
+
+impl Endpoint {
+    // The daemon health-check URL, e.g. `http://localhost:8080/api/health`.
+    pub fn health_url(&self) -> String {
+        format!("{}://{}:{}/api/health", self.scheme, self.host, self.port)
+    }
+
+    // The `host:port` pair used to probe local port availability.
+    pub fn bind_addr(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+impl Settings {
+    // Load the settings from `settings.toml` in the given config dir, falling
+    // back to defaults when the file is missing or cannot be parsed.
+    pub fn load(config_dir: &std::path::Path) -> Self {
+        let path = config_dir.join("settings.toml");
+        match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_default(),
+            Err(_) => Self::default(),
+        }
+    }
+
+    // Persist the settings to `settings.toml`, creating the config dir if needed.
+    pub fn save(&self, config_dir: &std::path::Path) -> Result<(), String> {
+        fs::create_dir_all(config_dir).map_err(|e| e.to_string())?;
+        let contents = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(config_dir.join("settings.toml"), contents).map_err(|e| e.to_string())
+    }
+}